@@ -1,10 +1,63 @@
 use crate::MAVLinkV2MessageRaw;
 
+#[cfg(feature = "std")]
 use std::time::SystemTime;
-use std::{collections::HashMap, sync::Mutex};
+// NOTE: the timestamp source is now pluggable, but the signing state still relies on
+// `std::collections::HashMap` and `std::sync::Mutex`, which are unavailable under `no_std`
+// (`HashMap` is not in `alloc`). A full `no_std` build is a separate follow-up that needs these
+// swapped for an `alloc` `BTreeMap` and a `no_std`-capable mutex.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use crate::MAVLINK_IFLAG_SIGNED;
 
+/// Predicate consulted for unsigned messages, i.e. those without the
+/// [`MAVLINK_IFLAG_SIGNED`] flag set.
+///
+/// It is called with the message id, source system id and source component id and
+/// returns `true` if the unsigned message should be accepted nonetheless. This allows
+/// a small allowlist (e.g. `RADIO_STATUS` from a telemetry radio that cannot sign) to
+/// pass while everything else is rejected. Signed messages that fail signature or
+/// timestamp verification are always rejected and never reach this predicate.
+pub type AcceptUnsignedCallback = Arc<dyn Fn(u32, u8, u8) -> bool + Send + Sync>;
+
+/// Source of the current MAVLink signing timestamp.
+///
+/// The value is expressed in the MAVLink signing time unit: the number of 10 µs ticks since
+/// 1st January 2015 GMT. Only the lower 48 bits are used by the protocol (they overflow in 2104).
+///
+/// The default [`SystemTimeSource`] reads [`std::time::SystemTime`] and is only available with the
+/// `std` feature. The trait itself has no dependency on `std`, so a custom implementation backed by
+/// a hardware RTC or a monotonic tick counter can be supplied to
+/// [`SigningData::from_config_with_timestamp_source`] to decouple signing from the system clock.
+pub trait TimestampSource: Send + Sync {
+    /// Returns the current timestamp in units of 10 µs ticks since 1st January 2015 GMT.
+    fn timestamp(&self) -> u64;
+}
+
+/// [`TimestampSource`] backed by [`std::time::SystemTime`].
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemTimeSource;
+
+#[cfg(feature = "std")]
+impl TimestampSource for SystemTimeSource {
+    fn timestamp(&self) -> u64 {
+        // fallback to 0 if the system time appears to be before epoch
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|n| n.as_micros())
+            .unwrap_or(0);
+        // use 1st January 2015 GMT as offset, fallback to 0 if before that date, the used 48 bit of this will overflow in 2104
+        ((now
+            .checked_sub(1420070400u128 * 1000000u128)
+            .unwrap_or_default())
+            / 10u128) as u64
+    }
+}
+
 /// Configuration used for MAVLink 2 messages signing as defined in <https://mavlink.io/en/guide/message_signing.html>.
 ///
 /// To use a [`SigningConfig`] for sending and reciving messages create a [`SigningData`] object using `SigningData::from_config`.
@@ -17,12 +70,39 @@ use crate::MAVLINK_IFLAG_SIGNED;
 /// let sign_data = SigningData::from_config(config);
 /// ```
 ///
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct SigningConfig {
     secret_key: [u8; 32],
     link_id: u8,
     pub(crate) sign_outgoing: bool,
     pub(crate) allow_unsigned: bool,
+    pub(crate) accept_unsigned_callback: Option<AcceptUnsignedCallback>,
+    pub(crate) max_stream_timestamps: usize,
+    pub(crate) secondary_keys: Vec<[u8; 32]>,
+}
+
+/// Default upper bound on the number of tracked signing streams.
+///
+/// Mirrors the fixed-size `signing_streams` array used by the C implementations and bounds the
+/// memory a peer can make us allocate by injecting frames with fresh `(link_id, system, component)`
+/// triples.
+pub const DEFAULT_MAX_STREAM_TIMESTAMPS: usize = 16;
+
+impl core::fmt::Debug for SigningConfig {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SigningConfig")
+            .field("secret_key", &self.secret_key)
+            .field("link_id", &self.link_id)
+            .field("sign_outgoing", &self.sign_outgoing)
+            .field("allow_unsigned", &self.allow_unsigned)
+            .field(
+                "accept_unsigned_callback",
+                &self.accept_unsigned_callback.as_ref().map(|_| "<callback>"),
+            )
+            .field("max_stream_timestamps", &self.max_stream_timestamps)
+            .field("secondary_keys", &self.secondary_keys)
+            .finish()
+    }
 }
 
 // mutable state of signing per connection
@@ -31,12 +111,101 @@ pub(crate) struct SigningState {
     stream_timestamps: HashMap<(u8, u8, u8), u64>,
 }
 
+impl SigningState {
+    /// Records `timestamp` for `stream_key`, evicting the least-recently-updated stream first when
+    /// inserting a new stream into a table that is already at `max_streams` capacity.
+    ///
+    /// Eviction only drops the entry; the connection-wide `timestamp` high-water mark is left
+    /// untouched, so a later frame from an evicted stream is still subject to the "more than a
+    /// minute older than the connection-wide timestamp" rejection. A frame from an evicted stream
+    /// that is within that minute is treated as a new stream and accepted if its signature is valid.
+    fn record_stream_timestamp(
+        &mut self,
+        stream_key: (u8, u8, u8),
+        timestamp: u64,
+        max_streams: usize,
+    ) {
+        if !self.stream_timestamps.contains_key(&stream_key)
+            && self.stream_timestamps.len() >= max_streams
+        {
+            if let Some(evict_key) = self
+                .stream_timestamps
+                .iter()
+                .min_by_key(|(_, &ts)| ts)
+                .map(|(&key, _)| key)
+            {
+                self.stream_timestamps.remove(&evict_key);
+            }
+        }
+        self.stream_timestamps.insert(stream_key, timestamp);
+    }
+}
+
+/// Current version of the [`SigningStateSnapshot`] format.
+pub const SIGNING_STATE_SNAPSHOT_VERSION: u8 = 1;
+
+/// A single persisted per-stream timestamp entry, keyed by `(link_id, src_system, src_component)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreamTimestamp {
+    /// Signature link id of the stream.
+    pub link_id: u8,
+    /// Source system id of the stream.
+    pub src_system: u8,
+    /// Source component id of the stream.
+    pub src_component: u8,
+    /// Last accepted signing timestamp for this stream.
+    pub timestamp: u64,
+}
+
+/// Serializable, versioned snapshot of the mutable [`SigningState`].
+///
+/// Persisting this blob (e.g. to disk or flash) and restoring it with
+/// [`SigningData::from_config_and_state`] after a restart keeps our own outgoing timestamps
+/// monotonic and preserves replay protection, instead of resetting to 0 and relearning every
+/// stream from scratch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SigningStateSnapshot {
+    /// Format version, see [`SIGNING_STATE_SNAPSHOT_VERSION`].
+    pub version: u8,
+    /// Highest signing timestamp observed or emitted so far.
+    pub timestamp: u64,
+    /// Per-stream last-accepted timestamps.
+    pub stream_timestamps: Vec<StreamTimestamp>,
+}
+
+/// Error returned when restoring a [`SigningStateSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningStateError {
+    /// The snapshot was produced by an incompatible format version.
+    ///
+    /// Carries the version found in the snapshot; the expected version is
+    /// [`SIGNING_STATE_SNAPSHOT_VERSION`].
+    UnsupportedVersion(u8),
+}
+
+impl core::fmt::Display for SigningStateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported signing state snapshot version {version}, expected {SIGNING_STATE_SNAPSHOT_VERSION}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SigningStateError {}
+
 /// MAVLink 2 message signing data
 ///
 /// Contains a [`SigningConfig`] as well as a mutable state that is reused for all messages in a connection.  
 pub struct SigningData {
     pub(crate) config: SigningConfig,
     pub(crate) state: Mutex<SigningState>,
+    timestamp_source: Arc<dyn TimestampSource>,
 }
 
 impl SigningConfig {
@@ -55,35 +224,181 @@ impl SigningConfig {
             link_id,
             sign_outgoing,
             allow_unsigned,
+            accept_unsigned_callback: None,
+            max_stream_timestamps: DEFAULT_MAX_STREAM_TIMESTAMPS,
+            secondary_keys: Vec::new(),
         }
     }
+
+    /// Registers additional secret keys accepted when verifying incoming messages.
+    ///
+    /// During key rotation a fleet runs a mix of old and new secrets; registering the previous
+    /// key(s) here lets `verify_signature` accept a message signed with any of them, while
+    /// `sign_message` keeps using the primary `secret_key`. This enables a staged rollover without
+    /// dropping in-flight signed connections.
+    pub fn with_additional_keys(mut self, keys: Vec<[u8; 32]>) -> Self {
+        self.secondary_keys = keys;
+        self
+    }
+
+    /// The designated primary key, always used to sign outgoing messages.
+    pub(crate) fn primary_key(&self) -> &[u8; 32] {
+        &self.secret_key
+    }
+
+    /// Keys tried when verifying an incoming signature: the primary key first, followed by any
+    /// registered secondary keys for staged key rotation.
+    pub(crate) fn candidate_keys(&self) -> impl Iterator<Item = &[u8; 32]> {
+        core::iter::once(&self.secret_key).chain(self.secondary_keys.iter())
+    }
+
+    /// Sets the maximum number of streams tracked for replay protection.
+    ///
+    /// When the table is full the least-recently-updated stream is evicted to make room, keeping
+    /// verification memory bounded. A value of `0` is clamped to `1`.
+    pub fn with_max_stream_timestamps(mut self, max_stream_timestamps: usize) -> Self {
+        self.max_stream_timestamps = max_stream_timestamps.max(1);
+        self
+    }
+
+    /// Registers a per-message predicate that decides whether an unsigned message is accepted.
+    ///
+    /// When set, the callback takes precedence over `allow_unsigned`: it is consulted for every
+    /// unsigned message (one without the [`MAVLINK_IFLAG_SIGNED`] flag), receiving the message id,
+    /// source system id and source component id. Returning `true` accepts the message. Signed
+    /// messages that fail verification are always rejected.
+    pub fn with_accept_unsigned_callback(mut self, callback: AcceptUnsignedCallback) -> Self {
+        self.accept_unsigned_callback = Some(callback);
+        self
+    }
 }
 
 impl SigningData {
-    /// Initializes signing data from a given [`SigningConfig`]
+    /// Initializes signing data from a given [`SigningConfig`], using the system clock as
+    /// timestamp source.
+    #[cfg(feature = "std")]
     pub fn from_config(config: SigningConfig) -> Self {
+        Self::from_config_with_timestamp_source(config, Arc::new(SystemTimeSource))
+    }
+
+    /// Initializes signing data from a given [`SigningConfig`] and a custom [`TimestampSource`].
+    ///
+    /// Use this instead of [`SigningData::from_config`] to drive signing from a clock other than
+    /// [`std::time::SystemTime`], e.g. a hardware RTC or a monotonic tick counter.
+    pub fn from_config_with_timestamp_source(
+        config: SigningConfig,
+        timestamp_source: Arc<dyn TimestampSource>,
+    ) -> Self {
         Self {
             config,
             state: Mutex::new(SigningState {
                 timestamp: 0,
                 stream_timestamps: HashMap::new(),
             }),
+            timestamp_source,
+        }
+    }
+
+    /// Initializes signing data from a given [`SigningConfig`] and a previously saved
+    /// [`SigningStateSnapshot`], using the system clock as timestamp source.
+    ///
+    /// Returns [`SigningStateError::UnsupportedVersion`] if the snapshot was written by an
+    /// incompatible format version.
+    #[cfg(feature = "std")]
+    pub fn from_config_and_state(
+        config: SigningConfig,
+        snapshot: SigningStateSnapshot,
+    ) -> Result<Self, SigningStateError> {
+        Self::from_config_and_state_with_timestamp_source(
+            config,
+            snapshot,
+            Arc::new(SystemTimeSource),
+        )
+    }
+
+    /// Initializes signing data from a given [`SigningConfig`], a previously saved
+    /// [`SigningStateSnapshot`] and a custom [`TimestampSource`].
+    ///
+    /// Callers can reload the snapshot from non-volatile storage on boot and resume with replay
+    /// protection intact. The snapshot's [`version`](SigningStateSnapshot::version) must equal
+    /// [`SIGNING_STATE_SNAPSHOT_VERSION`], otherwise [`SigningStateError::UnsupportedVersion`] is
+    /// returned. Restored streams are clamped to `config.max_stream_timestamps`, keeping the most
+    /// recently updated ones, so a snapshot can never reintroduce an over-capacity table.
+    pub fn from_config_and_state_with_timestamp_source(
+        config: SigningConfig,
+        snapshot: SigningStateSnapshot,
+        timestamp_source: Arc<dyn TimestampSource>,
+    ) -> Result<Self, SigningStateError> {
+        if snapshot.version != SIGNING_STATE_SNAPSHOT_VERSION {
+            return Err(SigningStateError::UnsupportedVersion(snapshot.version));
+        }
+        let mut entries = snapshot.stream_timestamps;
+        if entries.len() > config.max_stream_timestamps {
+            // keep the most recently updated streams, matching the LRU eviction policy
+            entries.sort_unstable_by(|a, b| b.timestamp.cmp(&a.timestamp));
+            entries.truncate(config.max_stream_timestamps);
+        }
+        let stream_timestamps = entries
+            .into_iter()
+            .map(|entry| {
+                (
+                    (entry.link_id, entry.src_system, entry.src_component),
+                    entry.timestamp,
+                )
+            })
+            .collect();
+        Ok(Self {
+            config,
+            state: Mutex::new(SigningState {
+                timestamp: snapshot.timestamp,
+                stream_timestamps,
+            }),
+            timestamp_source,
+        })
+    }
+
+    /// Captures the current mutable [`SigningState`] into a serializable, versioned snapshot.
+    ///
+    /// Callers can persist the returned value and later restore it with
+    /// [`SigningData::from_config_and_state`]. Saving it periodically keeps the persisted timestamp
+    /// close to the live one.
+    pub fn snapshot_state(&self) -> SigningStateSnapshot {
+        let state = self
+            .state
+            .lock()
+            .expect("Code holding MutexGuard should not panic.");
+        SigningStateSnapshot {
+            version: SIGNING_STATE_SNAPSHOT_VERSION,
+            timestamp: state.timestamp,
+            stream_timestamps: state
+                .stream_timestamps
+                .iter()
+                .map(|(&(link_id, src_system, src_component), &timestamp)| StreamTimestamp {
+                    link_id,
+                    src_system,
+                    src_component,
+                    timestamp,
+                })
+                .collect(),
         }
     }
 
     /// Verify the signature of a MAVLink 2 message.
     ///
-    /// This respects the `allow_unsigned` parameter in [`SigningConfig`].
+    /// Signed messages are accepted only if they pass the timestamp and signature checks. For
+    /// unsigned messages the decision is made by the [`AcceptUnsignedCallback`] when one is
+    /// configured on the [`SigningConfig`], otherwise it falls back to the `allow_unsigned` flag.
     pub fn verify_signature(&self, message: &MAVLinkV2MessageRaw) -> bool {
         // The code that holds the mutex lock is not expected to panic, therefore the expect is justified.
-        // The only issue that might cause a panic, presuming the opertions on the message buffer are sound,
-        // is the `SystemTime::now()` call in `get_current_timestamp()`.
+        // Presuming the operations on the message buffer are sound and neither the configured
+        // `TimestampSource` nor the `AcceptUnsignedCallback` panics, there is no panic-capable call
+        // while the lock is held.
         let mut state = self
             .state
             .lock()
             .expect("Code holding MutexGuard should not panic.");
         if message.incompatibility_flags() & MAVLINK_IFLAG_SIGNED > 0 {
-            state.timestamp = u64::max(state.timestamp, Self::get_current_timestamp());
+            state.timestamp = u64::max(state.timestamp, self.timestamp_source.timestamp());
             let timestamp = message.signature_timestamp();
             let src_system = message.system_id();
             let src_component = message.component_id();
@@ -103,17 +418,40 @@ impl SigningData {
                 }
             }
 
-            let mut signature_buffer = [0u8; 6];
-            message.calculate_signature(&self.config.secret_key, &mut signature_buffer);
-            let result = signature_buffer == message.signature_value();
+            // accept the message if it validates against the primary key or any registered
+            // secondary key, so a shared secret can be rotated across the fleet without a hard cutover
+            let result = self.config.candidate_keys().any(|key| {
+                let mut signature_buffer = [0u8; 6];
+                message.calculate_signature(key, &mut signature_buffer);
+                signature_buffer == message.signature_value()
+            });
             if result {
                 // if signature is valid update timestamps
-                state.stream_timestamps.insert(stream_key, timestamp);
+                state.record_stream_timestamp(
+                    stream_key,
+                    timestamp,
+                    self.config.max_stream_timestamps,
+                );
                 state.timestamp = u64::max(state.timestamp, timestamp)
             }
             result
         } else {
-            self.config.allow_unsigned
+            self.accept_unsigned(message)
+        }
+    }
+
+    /// Decides whether a message without the [`MAVLINK_IFLAG_SIGNED`] flag should be accepted.
+    ///
+    /// Consults the [`AcceptUnsignedCallback`] if one is configured, otherwise falls back to the
+    /// coarse `allow_unsigned` flag.
+    fn accept_unsigned(&self, message: &MAVLinkV2MessageRaw) -> bool {
+        match &self.config.accept_unsigned_callback {
+            Some(callback) => callback(
+                message.message_id(),
+                message.system_id(),
+                message.component_id(),
+            ),
+            None => self.config.allow_unsigned,
         }
     }
 
@@ -121,13 +459,13 @@ impl SigningData {
     pub fn sign_message(&self, message: &mut MAVLinkV2MessageRaw) {
         if message.incompatibility_flags() & MAVLINK_IFLAG_SIGNED > 0 {
             // The code that holds the mutex lock is not expected to panic, therefore the expect is justified.
-            // The only issue that might cause a panic, presuming the opertions on the message buffer are sound,
-            // is the `SystemTime::now()` call in `get_current_timestamp()`.
+            // Presuming the operations on the message buffer are sound and the configured `TimestampSource`
+            // does not panic, there is no panic-capable call while the lock is held.
             let mut state = self
                 .state
                 .lock()
                 .expect("Code holding MutexGuard should not panic.");
-            state.timestamp = u64::max(state.timestamp, Self::get_current_timestamp());
+            state.timestamp = u64::max(state.timestamp, self.timestamp_source.timestamp());
             let ts_bytes = u64::to_le_bytes(state.timestamp);
             message
                 .signature_timestamp_bytes_mut()
@@ -135,7 +473,7 @@ impl SigningData {
             *message.signature_link_id_mut() = self.config.link_id;
 
             let mut signature_buffer = [0u8; 6];
-            message.calculate_signature(&self.config.secret_key, &mut signature_buffer);
+            message.calculate_signature(self.config.primary_key(), &mut signature_buffer);
 
             message
                 .signature_value_mut()
@@ -143,17 +481,145 @@ impl SigningData {
             state.timestamp += 1;
         }
     }
+}
 
-    fn get_current_timestamp() -> u64 {
-        // fallback to 0 if the system time appears to be before epoch
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|n| n.as_micros())
-            .unwrap_or(0);
-        // use 1st January 2015 GMT as offset, fallback to 0 if before that date, the used 48 bit of this will overflow in 2104
-        ((now
-            .checked_sub(1420070400u128 * 1000000u128)
-            .unwrap_or_default())
-            / 10u128) as u64
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SigningConfig {
+        SigningConfig::new([0u8; 32], 0, true, false)
+    }
+
+    #[test]
+    fn snapshot_restore_round_trip_preserves_replay_state() {
+        let data = SigningData::from_config(config());
+        {
+            let mut state = data.state.lock().unwrap();
+            state.timestamp = 5_000;
+            state.record_stream_timestamp((1, 2, 3), 4_200, DEFAULT_MAX_STREAM_TIMESTAMPS);
+            state.record_stream_timestamp((4, 5, 6), 4_800, DEFAULT_MAX_STREAM_TIMESTAMPS);
+        }
+
+        let snapshot = data.snapshot_state();
+        assert_eq!(snapshot.version, SIGNING_STATE_SNAPSHOT_VERSION);
+
+        let restored = SigningData::from_config_and_state(config(), snapshot).unwrap();
+        let state = restored.state.lock().unwrap();
+        // the high-water mark and per-stream timestamps survive the round trip, so a replayed
+        // frame with an older timestamp is still rejected after a restart
+        assert_eq!(state.timestamp, 5_000);
+        assert_eq!(state.stream_timestamps.get(&(1, 2, 3)), Some(&4_200));
+        assert_eq!(state.stream_timestamps.get(&(4, 5, 6)), Some(&4_800));
+    }
+
+    #[test]
+    fn restore_rejects_incompatible_version() {
+        let snapshot = SigningStateSnapshot {
+            version: SIGNING_STATE_SNAPSHOT_VERSION.wrapping_add(1),
+            timestamp: 0,
+            stream_timestamps: Vec::new(),
+        };
+        let err = SigningData::from_config_and_state(config(), snapshot).unwrap_err();
+        assert_eq!(
+            err,
+            SigningStateError::UnsupportedVersion(SIGNING_STATE_SNAPSHOT_VERSION.wrapping_add(1))
+        );
+    }
+
+    #[test]
+    fn restore_clamps_to_max_stream_timestamps() {
+        let config = config().with_max_stream_timestamps(2);
+        let snapshot = SigningStateSnapshot {
+            version: SIGNING_STATE_SNAPSHOT_VERSION,
+            timestamp: 100,
+            stream_timestamps: vec![
+                StreamTimestamp {
+                    link_id: 0,
+                    src_system: 0,
+                    src_component: 1,
+                    timestamp: 10,
+                },
+                StreamTimestamp {
+                    link_id: 0,
+                    src_system: 0,
+                    src_component: 2,
+                    timestamp: 30,
+                },
+                StreamTimestamp {
+                    link_id: 0,
+                    src_system: 0,
+                    src_component: 3,
+                    timestamp: 20,
+                },
+            ],
+        };
+
+        let restored = SigningData::from_config_and_state(config, snapshot).unwrap();
+        let state = restored.state.lock().unwrap();
+        // only the two most recently updated streams are kept
+        assert_eq!(state.stream_timestamps.len(), 2);
+        assert!(state.stream_timestamps.contains_key(&(0, 0, 2)));
+        assert!(state.stream_timestamps.contains_key(&(0, 0, 3)));
+        assert!(!state.stream_timestamps.contains_key(&(0, 0, 1)));
+    }
+
+    #[test]
+    fn eviction_at_capacity_drops_least_recently_updated() {
+        let mut state = SigningState {
+            timestamp: 0,
+            stream_timestamps: HashMap::new(),
+        };
+        state.record_stream_timestamp((0, 0, 1), 10, 3);
+        state.record_stream_timestamp((0, 0, 2), 30, 3);
+        state.record_stream_timestamp((0, 0, 3), 20, 3);
+
+        // updating an existing stream at capacity must not evict anything
+        state.record_stream_timestamp((0, 0, 1), 40, 3);
+        assert_eq!(state.stream_timestamps.len(), 3);
+
+        // a genuinely new stream evicts the least-recently-updated one, i.e. (0, 0, 3) @ 20
+        state.record_stream_timestamp((0, 0, 4), 50, 3);
+        assert_eq!(state.stream_timestamps.len(), 3);
+        assert!(!state.stream_timestamps.contains_key(&(0, 0, 3)));
+        assert!(state.stream_timestamps.contains_key(&(0, 0, 4)));
+    }
+
+    #[test]
+    fn eviction_preserves_high_water_mark_so_old_frames_stay_rejected() {
+        // the ">1 minute older than the newest stream" check in `verify_signature` relies solely
+        // on `timestamp`; eviction must leave it untouched so an evicted stream cannot be replayed
+        // as a brand-new one with a timestamp more than a minute older than it
+        let mut state = SigningState {
+            timestamp: 10_000_000,
+            stream_timestamps: HashMap::new(),
+        };
+        state.record_stream_timestamp((0, 0, 1), 9_000_000, 1);
+        state.record_stream_timestamp((0, 0, 2), 9_500_000, 1);
+
+        assert_eq!(state.timestamp, 10_000_000);
+        assert!(!state.stream_timestamps.contains_key(&(0, 0, 1)));
+
+        // a replayed old frame for the evicted stream hits the `None` branch and is still rejected,
+        // because it is more than a minute (60 * 1000 * 100 ticks) older than the high-water mark
+        let replayed_timestamp = 1_000u64;
+        assert!(replayed_timestamp + 60 * 1000 * 100 < state.timestamp);
+    }
+
+    #[test]
+    fn key_seam_offers_primary_then_secondaries_and_signs_with_primary() {
+        let primary = [1u8; 32];
+        let previous = [2u8; 32];
+        let config =
+            SigningConfig::new(primary, 0, true, false).with_additional_keys(vec![previous]);
+
+        // `verify_signature` tries these keys in order, so a message signed with either the current
+        // or a previous key is accepted during rotation
+        let candidates: Vec<[u8; 32]> = config.candidate_keys().copied().collect();
+        assert_eq!(candidates, vec![primary, previous]);
+
+        // `sign_message` always uses the designated primary key, so rotating in a new primary never
+        // produces frames signed with a retired secondary
+        assert_eq!(config.primary_key(), &primary);
     }
 }